@@ -1,10 +1,19 @@
 use clap::{App, Arg};
 use std::collections::HashMap;
+use std::thread;
 
 #[derive(Debug, PartialEq)]
 pub struct YakeArgs {
     pub target: String,
     pub params: HashMap<String, String>,
+    pub jobs: usize,
+    pub manifest_path: Option<String>,
+}
+
+/// Default `--jobs` value when the flag isn't given: the number of
+/// available CPUs, falling back to `1` if that can't be determined.
+fn default_jobs() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
 }
 
 pub fn create_cli_app() -> YakeArgs {
@@ -24,11 +33,28 @@ pub fn create_cli_app() -> YakeArgs {
             .multiple(true)
             .required(false)
             .requires("TARGET"))
+        .arg(Arg::with_name("jobs")
+            .help("Number of targets to run in parallel (defaults to the number of CPUs)")
+            .takes_value(true)
+            .short("j")
+            .long("jobs")
+            .required(false))
+        .arg(Arg::with_name("manifest-path")
+            .help("Path to the Yakefile to use, overriding the usual upward search")
+            .takes_value(true)
+            .long("manifest-path")
+            .required(false))
         .get_matches();
 
     let target = matches.value_of("TARGET").expect("No target specified").trim();
 
-    let mut args = YakeArgs { target: target.to_string(), params: HashMap::new() };
+    let jobs = matches.value_of("jobs")
+        .and_then(|jobs| jobs.trim().parse().ok())
+        .unwrap_or_else(default_jobs);
+
+    let manifest_path = matches.value_of("manifest-path").map(|path| path.trim().to_string());
+
+    let mut args = YakeArgs { target: target.to_string(), params: HashMap::new(), jobs, manifest_path };
 
     if let Some(parameter_values) = matches.values_of("param") {
         for param in parameter_values {
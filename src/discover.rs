@@ -0,0 +1,120 @@
+//! Locates the Yakefile to run, mirroring cargo's upward manifest search.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use error::{ErrorKind, YakeError};
+
+/// Names recognized as a Yakefile, checked in this order in each directory.
+const YAKEFILE_NAMES: [&str; 3] = ["Yakefile", "Yakefile.yml", "Yakefile.yaml"];
+
+/// Finds the Yakefile to use.
+///
+/// If `manifest_path` is given (from `--manifest-path`), it's used as-is
+/// and must point at an existing file. Otherwise searches upward from the
+/// current directory through each parent for one of `YAKEFILE_NAMES`,
+/// stopping at the filesystem root.
+pub fn find_yakefile(manifest_path: Option<&str>) -> Result<PathBuf, YakeError> {
+    if let Some(path) = manifest_path {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(YakeError::new(ErrorKind::FileNotFound, format!("{}", path.display())))
+        };
+    }
+
+    let cwd = env::current_dir().map_err(|e| {
+        YakeError::new(ErrorKind::FileNotFound, format!("Unable to determine current directory: {}", e))
+    })?;
+
+    search_upward(&cwd).ok_or_else(|| {
+        YakeError::new(ErrorKind::FileNotFound,
+                       "Could not find a Yakefile in the current directory or any parent.".to_string())
+    })
+}
+
+/// Walks from `start` up through each parent directory, returning the
+/// first `Yakefile`/`Yakefile.yml`/`Yakefile.yaml` found.
+fn search_upward(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+
+    while let Some(current) = dir {
+        for name in &YAKEFILE_NAMES {
+            let candidate = current.join(name);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+
+        dir = current.parent();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Creates a fresh, uniquely-named temp directory tree for a test to
+    /// play in, so tests can run concurrently without clobbering each other.
+    fn temp_dir(name: &str) -> PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = env::temp_dir().join(format!("yake_discover_test_{}_{}_{}", name, process::id(), n));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_search_upward_finds_yakefile_in_parent() {
+        let root = temp_dir("finds_in_parent");
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        File::create(root.join("Yakefile")).unwrap();
+
+        assert_eq!(search_upward(&nested), Some(root.join("Yakefile")));
+    }
+
+    #[test]
+    fn test_search_upward_accepts_yml_and_yaml_extensions() {
+        for name in &["Yakefile.yml", "Yakefile.yaml"] {
+            let root = temp_dir(name);
+            File::create(root.join(name)).unwrap();
+
+            assert_eq!(search_upward(&root), Some(root.join(name)));
+        }
+    }
+
+    #[test]
+    fn test_search_upward_returns_none_when_nothing_found() {
+        let root = temp_dir("nothing_found");
+
+        assert_eq!(search_upward(&root), None);
+    }
+
+    #[test]
+    fn test_find_yakefile_uses_manifest_path_override() {
+        let root = temp_dir("manifest_override");
+        let path = root.join("custom.yml");
+        File::create(&path).unwrap();
+
+        let found = find_yakefile(Some(path.to_str().unwrap())).unwrap();
+
+        assert_eq!(found, path);
+    }
+
+    #[test]
+    fn test_find_yakefile_errors_on_missing_manifest_path() {
+        let root = temp_dir("manifest_missing");
+        let path = root.join("missing.yml");
+
+        let err = find_yakefile(Some(path.to_str().unwrap())).unwrap_err();
+
+        assert_eq!(err.kind, ErrorKind::FileNotFound);
+    }
+}
@@ -0,0 +1,174 @@
+//! Runs a resolved dependency graph concurrently on a bounded worker pool.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use error::YakeError;
+
+/// Shared scheduling state, guarded by a single `Mutex` and woken up via
+/// `Condvar` whenever it changes.
+struct Schedule {
+    /// Remaining unfinished direct-dependency count per target.
+    in_degree: HashMap<String, usize>,
+    /// Targets that depend on a given target, i.e. the reverse edges.
+    dependents: HashMap<String, Vec<String>>,
+    /// Targets with in-degree zero, waiting for a free worker.
+    ready: VecDeque<String>,
+    /// Number of targets currently being run by a worker.
+    in_flight: usize,
+    /// The first error reported by `run_one`, if any.
+    error: Option<YakeError>,
+}
+
+/// Runs every target named in `order` via `run_one`, respecting the
+/// dependency edges in `direct_dependencies` (target name -> its direct
+/// dependency names, all of which must also appear in `order`).
+///
+/// Up to `jobs` targets run concurrently; a target is scheduled as soon as
+/// all of its direct dependencies have completed successfully. If
+/// `run_one` returns an `Err` for any target, no further targets are
+/// scheduled and that error is returned once the in-flight work has
+/// drained.
+pub fn run<F>(order: &[String],
+              direct_dependencies: &HashMap<String, Vec<String>>,
+              jobs: usize,
+              run_one: F)
+              -> Result<(), YakeError>
+    where F: Fn(&str) -> Result<(), YakeError> + Sync
+{
+    let jobs = jobs.max(1);
+
+    let mut in_degree = HashMap::new();
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    for name in order {
+        dependents.entry(name.clone()).or_default();
+    }
+    for name in order {
+        let deps = direct_dependencies.get(name).cloned().unwrap_or_else(Vec::new);
+        in_degree.insert(name.clone(), deps.len());
+        for dep in deps {
+            dependents.entry(dep).or_default().push(name.clone());
+        }
+    }
+
+    let ready = in_degree.iter()
+        .filter(|&(_, &count)| count == 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let schedule = Mutex::new(Schedule { in_degree, dependents, ready, in_flight: 0, error: None });
+    let condvar = Condvar::new();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| worker(&schedule, &condvar, &run_one));
+        }
+    });
+
+    match schedule.into_inner().unwrap().error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// A single worker thread's loop: pull a ready target, run it, then fold
+/// the result back into the shared `Schedule` and wake the others.
+fn worker<F>(schedule: &Mutex<Schedule>, condvar: &Condvar, run_one: &F)
+    where F: Fn(&str) -> Result<(), YakeError> + Sync
+{
+    loop {
+        let task = {
+            let mut state = schedule.lock().unwrap();
+            loop {
+                if state.error.is_some() {
+                    return;
+                }
+                if let Some(task) = state.ready.pop_front() {
+                    state.in_flight += 1;
+                    break task;
+                }
+                if state.in_flight == 0 {
+                    return;
+                }
+                state = condvar.wait(state).unwrap();
+            }
+        };
+
+        let result = run_one(&task);
+
+        let mut state = schedule.lock().unwrap();
+        state.in_flight -= 1;
+
+        match result {
+            Ok(()) => {
+                if let Some(dependents) = state.dependents.get(&task).cloned() {
+                    for dependent in dependents {
+                        if let Some(count) = state.in_degree.get_mut(&dependent) {
+                            *count -= 1;
+                            if *count == 0 {
+                                state.ready.push_back(dependent);
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                if state.error.is_none() {
+                    state.error = Some(e);
+                }
+            }
+        }
+
+        drop(state);
+        condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+    use error::ErrorKind;
+
+    #[test]
+    fn test_run_executes_every_target_once() {
+        let order = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut edges = HashMap::new();
+        edges.insert("a".to_string(), vec![]);
+        edges.insert("b".to_string(), vec!["a".to_string()]);
+        edges.insert("c".to_string(), vec!["a".to_string()]);
+
+        let seen = StdMutex::new(Vec::new());
+
+        run(&order, &edges, 2, |name| {
+            seen.lock().unwrap().push(name.to_string());
+            Ok(())
+        }).unwrap();
+
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort();
+        assert_eq!(seen, vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_run_stops_scheduling_after_first_failure() {
+        let order = vec!["a".to_string(), "b".to_string()];
+        let mut edges = HashMap::new();
+        edges.insert("a".to_string(), vec![]);
+        edges.insert("b".to_string(), vec!["a".to_string()]);
+
+        let seen = StdMutex::new(Vec::new());
+
+        let result = run(&order, &edges, 2, |name| {
+            seen.lock().unwrap().push(name.to_string());
+            if name == "a" {
+                Err(YakeError::new(ErrorKind::CommandFailed, "boom".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(seen.into_inner().unwrap(), vec!["a".to_string()]);
+    }
+}
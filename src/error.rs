@@ -0,0 +1,41 @@
+//! Structured errors for yake.
+
+use std::fmt;
+
+/// Broad category of a [`YakeError`], so callers can react differently to,
+/// say, a missing file versus a cyclic dependency.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ErrorKind {
+    /// The Yakefile itself could not be found.
+    FileNotFound,
+    /// The Yakefile's contents could not be parsed.
+    ParseError,
+    /// The requested target does not exist.
+    UnknownTarget,
+    /// A `depends` entry names a target that does not exist.
+    UnknownDependency,
+    /// A `depends` chain refers back to one of its own ancestors.
+    CyclicDependency,
+    /// A command exited with a non-zero status.
+    CommandFailed,
+}
+
+/// A yake error: a broad [`ErrorKind`] plus a human-readable `message`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct YakeError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl YakeError {
+    /// Builds a new `YakeError` of `kind` with the given `message`.
+    pub fn new(kind: ErrorKind, message: String) -> YakeError {
+        YakeError { kind, message }
+    }
+}
+
+impl fmt::Display for YakeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
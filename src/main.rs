@@ -9,26 +9,58 @@ extern crate serde_derive;
 extern crate serde_yaml;
 
 use args::create_cli_app;
+use error::{ErrorKind, YakeError};
+use std::env;
+use std::path::Path;
 use std::process::exit;
 use yaml::load_yml_from_file;
 
+mod discover;
+mod driver;
+mod error;
 mod yaml;
 pub mod yake;
 mod args;
+mod resolve;
+mod suggest;
+mod template;
 
 fn main() {
     let yake_args = create_cli_app();
 
-    let yake = load_yml_from_file("Yakefile");
-    match yake.has_target_name(&yake_args.target) {
-        Err(x) => {
-            eprintln!("Unknown target: '{}' Available targets are: {:?}",
-                      yake_args.target, x);
-            exit(1);
+    if let Err(e) = run(&yake_args) {
+        eprintln!("yake: {}", e);
+        exit(1);
+    }
+}
+
+fn run(yake_args: &args::YakeArgs) -> Result<(), YakeError> {
+    let yakefile = discover::find_yakefile(yake_args.manifest_path.as_deref())?;
+
+    if let Some(dir) = yakefile.parent().filter(|p| !p.as_os_str().is_empty()) {
+        env::set_current_dir(dir).map_err(|e| {
+            YakeError::new(ErrorKind::FileNotFound,
+                           format!("Unable to switch to {}: {}", dir.display(), e))
+        })?;
+    }
+
+    let filename = yakefile.file_name().ok_or_else(|| {
+        YakeError::new(ErrorKind::FileNotFound, format!("Invalid Yakefile path: {}", yakefile.display()))
+    })?;
+
+    let yake = load_yml_from_file(Path::new(filename))?;
+
+    if let Err(candidates) = yake.has_target_name(&yake_args.target) {
+        match suggest::suggest(&yake_args.target, &candidates) {
+            Some(closest) => eprintln!("Unknown target '{}'. Did you mean '{}'?",
+                                        yake_args.target, closest),
+            None => eprintln!("Unknown target: '{}' Available targets are: {:?}",
+                               yake_args.target, candidates),
         }
-        _ => (),
-    };
+        exit(1);
+    }
+
+    yake.execute(&yake_args.target, &yake_args.params, yake_args.jobs)?;
 
-    yake.execute(&yake_args.target)
-        .expect(format!("Execution of target: {} failed.", &yake_args.target).as_str());
+    Ok(())
 }
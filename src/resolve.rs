@@ -0,0 +1,129 @@
+//! Dependency resolution for yake targets.
+
+use std::collections::HashSet;
+use error::{ErrorKind, YakeError};
+use yake::Yake;
+
+/// Resolves the full dependency closure of `target_name` into a valid
+/// execution order, dependencies before dependents, with each target
+/// appearing exactly once.
+///
+/// Performs a depth-first traversal tracking a `visited` set (nodes already
+/// placed in the output) and a `stack` of the names on the current
+/// recursion path. A node is appended to the output on the way back out of
+/// the recursion, so the output is in postorder: every target comes after
+/// all of its dependencies.
+/// Encountering a node that is already on `stack` means a cycle; that is
+/// reported as an `Err` naming every target on the cycle, rather than
+/// recursing forever.
+pub fn resolve(yake: &Yake, target_name: &str) -> Result<Vec<String>, YakeError> {
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = Vec::new();
+
+    visit(yake, target_name, &mut visited, &mut stack, &mut order)?;
+
+    Ok(order)
+}
+
+/// Visits a single node of the dependency graph, recursing into its
+/// `depends` before appending it to `order`.
+fn visit(yake: &Yake,
+         target_name: &str,
+         visited: &mut HashSet<String>,
+         stack: &mut Vec<String>,
+         order: &mut Vec<String>)
+         -> Result<(), YakeError> {
+    if visited.contains(target_name) {
+        return Ok(());
+    }
+
+    if let Some(start) = stack.iter().position(|name| name == target_name) {
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(target_name.to_string());
+
+        return Err(YakeError::new(
+            ErrorKind::CyclicDependency,
+            format!("Cyclic dependency detected: {}", cycle.join(" -> ")),
+        ));
+    }
+
+    stack.push(target_name.to_string());
+
+    for dependency_name in yake.direct_dependencies(target_name)? {
+        visit(yake, &dependency_name, visited, stack, order)?;
+    }
+
+    stack.pop();
+    visited.insert(target_name.to_string());
+    order.push(target_name.to_string());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use yake::{Yake, YakeMeta, YakeTarget, YakeTargetMeta, YakeTargetType};
+
+    fn target(doc: &str, depends: Option<Vec<String>>) -> YakeTarget {
+        YakeTarget {
+            targets: None,
+            meta: YakeTargetMeta {
+                doc: doc.to_string(),
+                target_type: YakeTargetType::Cmd,
+                depends,
+                ignore_errors: None,
+            },
+            env: None,
+            exec: None,
+        }
+    }
+
+    fn yake(targets: HashMap<String, YakeTarget>) -> Yake {
+        Yake {
+            meta: YakeMeta { doc: "Bla".to_string(), version: "1.0.0".to_string() },
+            env: None,
+            targets,
+            fabricated: false,
+            all_targets: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_orders_transitive_dependencies() {
+        let targets: HashMap<String, YakeTarget> =
+            [("build".to_string(), target("build", Some(vec!["fmt".to_string()]))),
+                ("fmt".to_string(), target("fmt", Some(vec!["deps".to_string()]))),
+                ("deps".to_string(), target("deps", None))]
+                .iter().cloned().collect();
+
+        let order = resolve(&yake(targets), "build").unwrap();
+
+        assert_eq!(order, vec!["deps".to_string(), "fmt".to_string(), "build".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_detects_cycle() {
+        let targets: HashMap<String, YakeTarget> =
+            [("a".to_string(), target("a", Some(vec!["b".to_string()]))),
+                ("b".to_string(), target("b", Some(vec!["a".to_string()])))]
+                .iter().cloned().collect();
+
+        assert!(resolve(&yake(targets), "a").is_err());
+    }
+
+    #[test]
+    fn test_resolve_cycle_error_names_every_participating_target() {
+        let targets: HashMap<String, YakeTarget> =
+            [("a".to_string(), target("a", Some(vec!["b".to_string()]))),
+                ("b".to_string(), target("b", Some(vec!["c".to_string()]))),
+                ("c".to_string(), target("c", Some(vec!["a".to_string()])))]
+                .iter().cloned().collect();
+
+        let err = resolve(&yake(targets), "a").unwrap_err();
+
+        assert!(err.message.contains("a -> b -> c -> a"));
+    }
+}
@@ -0,0 +1,76 @@
+//! "Did you mean" suggestions for mistyped target names.
+
+/// Computes the Levenshtein edit distance between `a` and `b`: the minimum
+/// number of single-character insertions, deletions or substitutions
+/// needed to turn one into the other.
+///
+/// Uses the classic two-row dynamic-programming formulation, since only
+/// the previous row is ever needed to compute the next one.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    let mut current_row = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current_row[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            current_row[j] = *[previous_row[j] + 1,
+                                current_row[j - 1] + 1,
+                                previous_row[j - 1] + cost]
+                .iter()
+                .min()
+                .unwrap();
+        }
+
+        previous_row.clone_from(&current_row);
+    }
+
+    previous_row[b.len()]
+}
+
+/// Finds the target name in `candidates` closest to `name`, if any is
+/// close enough to plausibly be a typo.
+///
+/// A candidate counts as close enough when its edit distance is at most 3
+/// or at most a third of `name`'s length, matching cargo's `lev_distance`
+/// heuristic for "did you mean" suggestions.
+pub fn suggest(name: &str, candidates: &[String]) -> Option<String> {
+    let length = name.chars().count();
+
+    candidates.iter()
+        .map(|candidate| (candidate, edit_distance(name, candidate)))
+        .filter(|&(_, distance)| distance <= 3 || distance * 3 <= length)
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(candidate, _)| candidate.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("build", "build"), 0);
+        assert_eq!(edit_distance("buld", "build"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest_finds_close_typo() {
+        let candidates = vec!["build".to_string(), "test".to_string(), "deploy".to_string()];
+
+        assert_eq!(suggest("buld", &candidates), Some("build".to_string()));
+    }
+
+    #[test]
+    fn test_suggest_returns_none_when_nothing_close() {
+        let candidates = vec!["build".to_string(), "test".to_string(), "deploy".to_string()];
+
+        assert_eq!(suggest("xyz", &candidates), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Placeholder substitution for target commands.
+
+use std::collections::HashMap;
+use error::{ErrorKind, YakeError};
+
+/// Substitutes every `${name}` placeholder in `command` with the matching
+/// entry from `values`.
+///
+/// Returns an `Err` naming the placeholder if it has no corresponding
+/// entry, rather than passing an un-expanded `${name}` through to bash.
+pub fn substitute(command: &str, values: &HashMap<String, String>) -> Result<String, YakeError> {
+    let mut result = String::with_capacity(command.len());
+    let mut rest = command;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+
+        let after_start = &rest[start + 2..];
+        let end = after_start.find('}').ok_or_else(|| {
+            YakeError::new(ErrorKind::ParseError,
+                           format!("Unterminated placeholder in command: {}", command))
+        })?;
+
+        let name = &after_start[..end];
+        let value = values.get(name).ok_or_else(|| {
+            YakeError::new(ErrorKind::ParseError,
+                           format!("Unknown placeholder '${{{}}}' in command: {}", name, command))
+        })?;
+
+        result.push_str(value);
+        rest = &after_start[end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_replaces_known_placeholders() {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), "world".to_string());
+
+        assert_eq!(substitute("echo hello ${name}", &values).unwrap(), "echo hello world");
+    }
+
+    #[test]
+    fn test_substitute_errors_on_unknown_placeholder() {
+        let values = HashMap::new();
+
+        assert!(substitute("echo ${missing}", &values).is_err());
+    }
+}
@@ -2,6 +2,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use std::collections::HashMap;
 use std::process::{Command, Stdio};
+use driver;
+use error::{ErrorKind, YakeError};
+use resolve;
+use template;
 
 /// Represents the full yaml structure.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -15,15 +19,11 @@ pub struct Yake {
     /// Flag indicates, whether the object was fabricated already.
     /// Not deserialized from yaml.
     #[serde(skip)]
-    fabricated: bool,
+    pub(crate) fabricated: bool,
     /// Normalized, flattened map of all targets.
     /// Not deserialized from yaml.
     #[serde(skip)]
-    all_targets: HashMap<String, YakeTarget>,
-    /// Normalized, flattened map of all dependencies.
-    /// Not deserialized from yaml.
-    #[serde(skip)]
-    dependencies: HashMap<String, Vec<YakeTarget>>,
+    pub(crate) all_targets: HashMap<String, YakeTarget>,
 }
 
 /// Contains meta data for the yake object.
@@ -48,6 +48,9 @@ pub struct YakeTargetMeta {
     pub target_type: YakeTargetType,
     /// List of dependent targets
     pub depends: Option<Vec<String>>,
+    /// When `true`, a non-zero exit status from one of this target's
+    /// commands is ignored and doesn't stop the dependency chain.
+    pub ignore_errors: Option<bool>,
 }
 
 /// Defines a yake target. Can have sub-targets.
@@ -146,82 +149,119 @@ impl Yake {
         self.get_all_targets().get(&target_name.to_string()).cloned()
     }
 
-    /// Gets a normalized, flattened map of all dependencies for each target name.
-    fn get_all_dependencies(&self) -> HashMap<String, Vec<YakeTarget>> {
-        let mut ret: HashMap<String, Vec<YakeTarget>> = HashMap::new();
-        for (target_name, target) in self.get_all_targets() {
-            ret.insert(target_name.clone(), Vec::new());
-            for dependency_name in target.meta.depends.unwrap_or(vec![]).iter() {
-                let dep = self.get_target_by_name(dependency_name);
-                let dep_target = dep.expect(
-                    format!("Warning: Unknown dependency: {} in target: {}.",
-                            dependency_name,
-                            target_name).as_str()
-                );
-                ret.get_mut(&target_name).unwrap().push(dep_target);
-            }
-        }
-
-        ret
-    }
-
-    /// Gets a list of dependencies for a target name.
-    fn get_dependencies_by_name(&self, target_name: &str) -> Vec<YakeTarget> {
-        self.dependencies.get(target_name).unwrap().clone()
+    /// Gets the direct dependency names declared for a target.
+    ///
+    /// Used by the [`resolve`](../resolve/index.html) module to walk the
+    /// dependency graph one edge at a time. Returns an `Err` if
+    /// `target_name` isn't a known target.
+    pub(crate) fn direct_dependencies(&self, target_name: &str) -> Result<Vec<String>, YakeError> {
+        self.get_target_by_name(target_name)
+            .map(|target| target.meta.depends.unwrap_or_else(Vec::new))
+            .ok_or_else(|| {
+                YakeError::new(ErrorKind::UnknownDependency,
+                               format!("Unknown dependency: {}", target_name))
+            })
     }
 
     /// Creates some kind of cached / fabricated object
     /// This is possibly not useful at all.
     /// TODO: check whether it's needed or not.
-    pub fn fabricate(&self) -> Yake {
+    pub fn fabricate(&self) -> Result<Yake, YakeError> {
         if self.fabricated {
-            return self.clone();
+            return Ok(self.clone());
         }
 
         let y = Yake {
             all_targets: self.get_all_targets(),
-            dependencies: self.get_all_dependencies(),
             fabricated: true,
             ..self.clone()
         };
 
-        return y;
+        Ok(y)
+    }
+
+    /// Parses this target's `env` merged over the global `env` into a
+    /// `KEY=value` map, with the target's own entries winning on conflict.
+    /// Malformed entries (missing `=`) are skipped.
+    fn merge_env(&self, target: &YakeTarget) -> HashMap<String, String> {
+        let mut merged = parse_env_entries(self.env.as_ref());
+        merged.extend(parse_env_entries(target.env.as_ref()));
+        merged
+    }
+
+    /// Runs just `target_name`'s own commands, not its dependencies.
+    ///
+    /// Each command is passed through
+    /// [`template::substitute`](../template/fn.substitute.html) first, so
+    /// `${name}` placeholders are expanded from `params` and from the
+    /// target/global `env` entries, and is then run with that same `env`
+    /// set on the spawned process. Stops at the first command that exits
+    /// non-zero and reports it with [`ErrorKind::CommandFailed`], unless
+    /// the target sets `ignore_errors: true`.
+    fn run_target(&self, target_name: &str, params: &HashMap<String, String>) -> Result<(), YakeError> {
+        let target = self.get_target_by_name(target_name).unwrap();
+
+        let commands = match target.exec {
+            Some(ref commands) => commands,
+            None => return Ok(()),
+        };
+
+        let env = self.merge_env(&target);
+        let mut values = env.clone();
+        values.extend(params.clone());
+
+        for command in commands {
+            let command = template::substitute(command, &values)?;
+
+            println!("-- {}", command);
+            let status = Command::new("bash")
+                .arg("-c")
+                .arg(command.clone())
+                .envs(&env)
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .map_err(|e| {
+                    YakeError::new(ErrorKind::CommandFailed,
+                                   format!("Failed to execute command \"{}\" in target '{}': {}",
+                                           command, target_name, e))
+                })?;
+
+            if !status.success() && !target.meta.ignore_errors.unwrap_or(false) {
+                return Err(YakeError::new(
+                    ErrorKind::CommandFailed,
+                    format!("Command \"{}\" in target '{}' failed with exit code {}.",
+                            command, target_name, status.code().map_or("unknown".to_string(), |c| c.to_string())),
+                ));
+            }
+        }
+
+        Ok(())
     }
 
     /// Execute a target and it's dependencies.
-    pub fn execute(&self, target_name: &str) -> Result<String, String> {
+    ///
+    /// Resolves the full dependency closure of `target_name` via
+    /// [`resolve::resolve`](../resolve/fn.resolve.html) first, so transitive
+    /// dependencies are included, shared ones run only once, and a cyclic
+    /// `depends` chain is reported instead of mishandled. The resolved
+    /// targets are then handed to [`driver::run`](../driver/fn.run.html),
+    /// which runs up to `jobs` of them at a time as their dependencies
+    /// finish, stopping at the first failure.
+    pub fn execute(&self, target_name: &str, params: &HashMap<String, String>, jobs: usize) -> Result<String, YakeError> {
         if self.has_target_name(target_name).is_err() {
-            return Err(format!("Unknown target: {}", target_name).to_string());
+            return Err(YakeError::new(ErrorKind::UnknownTarget,
+                                       format!("Unknown target: {}", target_name)));
         }
 
-        let target = self.get_target_by_name(target_name).unwrap();
-        let dependencies = self.get_dependencies_by_name(target_name);
-
-        let run_target = |target: &YakeTarget| {
-            match target.exec {
-                Some(ref commands) => {
-                    for command in commands {
-                        println!("-- {}", command);
-                        Command::new("bash")
-                            .arg("-c")
-                            .arg(command.clone())
-                            .stdout(Stdio::inherit())
-                            .stderr(Stdio::inherit())
-                            .output()
-                            .expect(&format!("failed to execute command \"{}\"", command));
-                    }
-                },
-                _ => ()
-            }
-        };
+        let order = resolve::resolve(self, target_name)?;
 
-        // run dependencies first
-        for dep in dependencies {
-            run_target(&dep);
+        let mut edges = HashMap::new();
+        for name in &order {
+            edges.insert(name.clone(), self.direct_dependencies(name)?);
         }
 
-        // then run the actual target
-        run_target(&target);
+        driver::run(&order, &edges, jobs, |name| self.run_target(name, params))?;
 
         Ok("All cool".to_string())
     }
@@ -255,6 +295,20 @@ impl YakeTarget {
     }
 }
 
+/// Parses a list of `KEY=value` strings into a map, silently skipping any
+/// entry that has no `=`.
+fn parse_env_entries(entries: Option<&Vec<String>>) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+
+    for entry in entries.into_iter().flatten() {
+        if let [key, value] = entry.splitn(2, '=').collect::<Vec<&str>>().as_slice() {
+            map.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    map
+}
+
 #[allow(dead_code)]
 fn get_all_targets<'a>(yake: &'a Yake) -> Vec<&'a YakeTarget> {
     let mut ret = Vec::new();
@@ -278,6 +332,7 @@ mod tests {
                       doc: "Huhu".to_string(),
                       target_type: YakeTargetType::Cmd,
                       depends: None,
+                      ignore_errors: None,
                   },
                   env: None,
                   exec: None,
@@ -289,17 +344,14 @@ mod tests {
                          doc: "Huhu".to_string(),
                          target_type: YakeTargetType::Cmd,
                          depends: Some(vec!["base".to_string()]),
+                         ignore_errors: None,
                      },
                      env: None,
                      exec: None,
                  })].iter().cloned().collect();
 
-        let mut dependencies = HashMap::new();
-        dependencies.insert("test".to_string(), vec![targets.get(&"base".to_string()).unwrap().clone()]);
-
         let yake = Yake {
             targets,
-            dependencies,
             env: None,
             meta: YakeMeta {
                 doc: "Bla".to_string(),
@@ -312,4 +364,86 @@ mod tests {
 
         let _targets = get_all_targets(&yake);
     }
+
+    use std::fs;
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Path to a scratch file unique to this test run, for a command to
+    /// write into so the test can assert on what actually reached the
+    /// spawned process.
+    fn scratch_file(name: &str) -> std::path::PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+        std::env::temp_dir().join(format!("yake_run_target_test_{}_{}_{}", name, process::id(), n))
+    }
+
+    fn cmd_target(exec: Vec<&str>, ignore_errors: Option<bool>, env: Option<Vec<String>>) -> YakeTarget {
+        YakeTarget {
+            targets: None,
+            meta: YakeTargetMeta {
+                doc: "doc".to_string(),
+                target_type: YakeTargetType::Cmd,
+                depends: None,
+                ignore_errors,
+            },
+            env,
+            exec: Some(exec.into_iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    fn yake_with(target: YakeTarget, env: Option<Vec<String>>) -> Yake {
+        let mut targets = HashMap::new();
+        targets.insert("run".to_string(), target);
+
+        Yake {
+            targets: targets.clone(),
+            env,
+            meta: YakeMeta { doc: "Bla".to_string(), version: "1.0.0".to_string() },
+            fabricated: false,
+            all_targets: targets,
+        }
+    }
+
+    #[test]
+    fn test_execute_runs_exec_commands_successfully() {
+        let yake = yake_with(cmd_target(vec!["true"], None, None), None);
+
+        assert!(yake.execute("run", &HashMap::new(), 1).is_ok());
+    }
+
+    #[test]
+    fn test_execute_fails_on_nonzero_exit() {
+        let yake = yake_with(cmd_target(vec!["false"], None, None), None);
+
+        let err = yake.execute("run", &HashMap::new(), 1).unwrap_err();
+
+        assert_eq!(err.kind, ErrorKind::CommandFailed);
+    }
+
+    #[test]
+    fn test_execute_ignores_failure_when_ignore_errors_is_set() {
+        let yake = yake_with(cmd_target(vec!["false"], Some(true), None), None);
+
+        assert!(yake.execute("run", &HashMap::new(), 1).is_ok());
+    }
+
+    #[test]
+    fn test_execute_applies_env_with_target_overriding_global() {
+        let path = scratch_file("env_precedence");
+        let path_str = path.to_str().unwrap().to_string();
+
+        let target = cmd_target(vec![&format!("echo \"$GREETING $NAME\" > {}", path_str)],
+                                 None,
+                                 Some(vec!["GREETING=hi".to_string()]));
+        let yake = yake_with(target, Some(vec!["GREETING=hello".to_string(), "NAME=world".to_string()]));
+
+        yake.execute("run", &HashMap::new(), 1).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(contents.trim(), "hi world");
+    }
 }
\ No newline at end of file
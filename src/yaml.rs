@@ -1,15 +1,22 @@
 use serde_yaml;
 use std::fs::File;
 use std::io::prelude::*;
+use std::path::Path;
+use error::{ErrorKind, YakeError};
 use yake::Yake;
 
-pub fn load_yml_from_file(filename: &str) -> Yake {
-    let mut f = File::open(filename).expect("File not found.");
+/// Loads and parses a Yakefile, then fabricates the flattened target map
+/// used for execution.
+pub fn load_yml_from_file(path: &Path) -> Result<Yake, YakeError> {
+    let mut f = File::open(path)
+        .map_err(|e| YakeError::new(ErrorKind::FileNotFound, format!("{}: {}", path.display(), e)))?;
     let mut contents = String::new();
 
-    f.read_to_string(&mut contents).expect("Error while reading file.");
+    f.read_to_string(&mut contents)
+        .map_err(|e| YakeError::new(ErrorKind::ParseError, format!("Error while reading {}: {}", path.display(), e)))?;
 
-    let yake: Yake = serde_yaml::from_str(&contents).expect("Unable to parse");
+    let yake: Yake = serde_yaml::from_str(&contents)
+        .map_err(|e| YakeError::new(ErrorKind::ParseError, format!("Unable to parse {}: {}", path.display(), e)))?;
 
     yake.fabricate()
 }
\ No newline at end of file